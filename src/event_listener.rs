@@ -1,11 +1,12 @@
 use crate::shared::{get_socket_path, SocketType, WorkspaceId};
+use futures_core::Stream;
 use regex::{Regex, RegexSet};
 use std::io;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::net::UnixStream;
 
 /// This tuple struct holds window event data
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WindowEventData(
     /// The window class
     pub String,
@@ -14,7 +15,7 @@ pub struct WindowEventData(
 );
 
 /// This tuple struct holds monitor event data
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MonitorEventData(
     /// The monitor name
     pub String,
@@ -22,17 +23,55 @@ pub struct MonitorEventData(
     pub WorkspaceId,
 );
 
+/// This tuple struct holds keyboard layout event data
+#[derive(Debug, Clone)]
+pub struct LayoutEvent(
+    /// The keyboard name
+    pub String,
+    /// The layout name
+    pub String,
+);
+
+/// This tuple struct holds the data for a window being opened
+#[derive(Debug, Clone)]
+pub struct WindowOpenEvent(
+    /// The window address
+    pub String,
+    /// The workspace the window opened on
+    pub String,
+    /// The window class
+    pub String,
+    /// The window title
+    pub String,
+);
+
+/// This tuple struct holds the data for a window being moved
+#[derive(Debug, Clone)]
+pub struct WindowMoveEvent(
+    /// The window address
+    pub String,
+    /// The workspace the window was moved to
+    pub String,
+);
+
 /// This enum holds every event type
 #[derive(Debug)]
-enum Event {
+pub enum Event {
     WorkspaceChanged(WorkspaceId),
     WorkspaceDeleted(WorkspaceId),
     WorkspaceAdded(WorkspaceId),
+    WorkspaceMoved(MonitorEventData),
     ActiveWindowChanged(Option<WindowEventData>),
     ActiveMonitorChanged(MonitorEventData),
     FullscreenStateChanged(bool),
     MonitorAdded(String),
     MonitorRemoved(String),
+    LayoutChanged(LayoutEvent),
+    SubmapChanged(String),
+    WindowOpened(WindowOpenEvent),
+    WindowClosed(String),
+    WindowMoved(WindowMoveEvent),
+    Urgent(String),
 }
 
 /// This internal function parses event strings
@@ -46,7 +85,14 @@ fn event_parser(event: String) -> io::Result<Vec<Event>> {
             r"activewindow>>(?P<class>.*),(?P<title>.*)",
             r"fullscreen>>(?P<state>0|1)",
             r"monitorremoved>>(?P<monitor>.*)",
-            r"monitoradded>>(?P<monitor>.*)"
+            r"monitoradded>>(?P<monitor>.*)",
+            r"activelayout>>(?P<keyboard>[^,]*),(?P<layout>.*)",
+            r"submap>>(?P<submap>.*)",
+            r"openwindow>>(?P<address>0x[0-9a-f]+),(?P<workspace>[^,]*),(?P<class>[^,]*),(?P<title>.*)",
+            r"closewindow>>(?P<address>0x[0-9a-f]+)",
+            r"movewindow>>(?P<address>0x[0-9a-f]+),(?P<workspace>.*)",
+            r"moveworkspace>>(?P<workspace>[0-9]{1,2}),(?P<monitor>.*)",
+            r"urgent>>(?P<address>0x[0-9a-f]+)"
         ])
         .unwrap();
         static ref EVENT_REGEXES: Vec<Regex> = EVENT_SET
@@ -128,6 +174,61 @@ fn event_parser(event: String) -> io::Result<Vec<Event>> {
                     let monitor = &captures["monitor"];
                     events.push(Event::MonitorAdded(monitor.to_string()));
                 }
+                8 => {
+                    // LayoutChanged
+                    let keyboard = &captures["keyboard"];
+                    let layout = &captures["layout"];
+                    events.push(Event::LayoutChanged(LayoutEvent(
+                        keyboard.to_string(),
+                        layout.to_string(),
+                    )));
+                }
+                9 => {
+                    // SubmapChanged
+                    let submap = &captures["submap"];
+                    events.push(Event::SubmapChanged(submap.to_string()));
+                }
+                10 => {
+                    // WindowOpened
+                    let address = &captures["address"];
+                    let workspace = &captures["workspace"];
+                    let class = &captures["class"];
+                    let title = &captures["title"];
+                    events.push(Event::WindowOpened(WindowOpenEvent(
+                        address.to_string(),
+                        workspace.to_string(),
+                        class.to_string(),
+                        title.to_string(),
+                    )));
+                }
+                11 => {
+                    // WindowClosed
+                    let address = &captures["address"];
+                    events.push(Event::WindowClosed(address.to_string()));
+                }
+                12 => {
+                    // WindowMoved
+                    let address = &captures["address"];
+                    let workspace = &captures["workspace"];
+                    events.push(Event::WindowMoved(WindowMoveEvent(
+                        address.to_string(),
+                        workspace.to_string(),
+                    )));
+                }
+                13 => {
+                    // WorkspaceMoved
+                    let workspace = captures["workspace"].parse::<u8>().unwrap();
+                    let monitor = &captures["monitor"];
+                    events.push(Event::WorkspaceMoved(MonitorEventData(
+                        monitor.to_string(),
+                        workspace,
+                    )));
+                }
+                14 => {
+                    // Urgent
+                    let address = &captures["address"];
+                    events.push(Event::Urgent(address.to_string()));
+                }
                 _ => panic!("How did this happen?"),
             }
         } else {
@@ -138,6 +239,92 @@ fn event_parser(event: String) -> io::Result<Vec<Event>> {
     Ok(events)
 }
 
+/// This struct holds the state that is tracked and kept up to date across
+/// event dispatches so that `_mut` handlers don't have to reconstruct it
+/// themselves (e.g. with their own `Rc<RefCell<...>>`)
+#[derive(Debug, Clone)]
+pub struct State {
+    /// The active workspace id
+    pub active_workspace: WorkspaceId,
+    /// The active monitor name
+    pub active_monitor: String,
+    /// The active window, if any
+    pub active_window: Option<WindowEventData>,
+    /// Whether the active window is currently fullscreen
+    pub fullscreen: bool,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            active_workspace: 1,
+            active_monitor: String::new(),
+            active_window: None,
+            fullscreen: false,
+        }
+    }
+}
+
+impl State {
+    /// Applies an already-parsed event to this state, keeping it current
+    fn update(&mut self, event: &Event) {
+        match event {
+            Event::WorkspaceChanged(id) => self.active_workspace = *id,
+            Event::ActiveMonitorChanged(MonitorEventData(monitor, workspace)) => {
+                self.active_monitor = monitor.clone();
+                self.active_workspace = *workspace;
+            }
+            Event::ActiveWindowChanged(data) => self.active_window = data.clone(),
+            Event::FullscreenStateChanged(state) => self.fullscreen = *state,
+            _ => {}
+        }
+    }
+}
+
+type RegularCallback<T> = Box<dyn FnMut(T) + Send>;
+type MutableCallback<T> = Box<dyn FnMut(T, &mut State) + Send>;
+
+/// A handler slot: either a plain callback or one that also receives the
+/// listener's shared [`State`]
+enum Handler<T> {
+    Regular(RegularCallback<T>),
+    Mutable(MutableCallback<T>),
+}
+
+impl<T> Handler<T> {
+    fn call(&mut self, data: T, state: &mut State) {
+        match self {
+            Handler::Regular(f) => f(data),
+            Handler::Mutable(f) => f(data, state),
+        }
+    }
+}
+
+/// Identifies which per-event registry a [`HandlerId`] belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HandlerCategory {
+    WorkspaceChanged,
+    WorkspaceAdded,
+    WorkspaceDestroyed,
+    WorkspaceMoved,
+    ActiveMonitorChanged,
+    ActiveWindowChanged,
+    FullscreenStateChanged,
+    MonitorAdded,
+    MonitorRemoved,
+    LayoutChanged,
+    SubmapChanged,
+    WindowOpened,
+    WindowClosed,
+    WindowMoved,
+    UrgentState,
+}
+
+/// A handle returned by `add_*_handler` that can later be passed to
+/// [`EventListener::remove_handler`] to unregister that callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerId(usize, HandlerCategory);
+
 ///
 /// # The Event Listener
 ///
@@ -147,127 +334,579 @@ fn event_parser(event: String) -> io::Result<Vec<Event>> {
 ///
 /// ```rust
 /// let mut listener = EventListener::new(); // creates a new listener
-/// listener.add_insert_event_name_here_handler(&|data| do_something_with(data));
+/// listener.add_insert_event_name_here_handler(|data| do_something_with(data));
 /// listener.start_listener_blocking(); // or `.start_listener().await` if async
 /// ```
-pub struct EventListener<'a> {
-    workspace_changed_events: Vec<&'a dyn Fn(WorkspaceId)>,
-    workspace_added_events: Vec<&'a dyn Fn(WorkspaceId)>,
-    workspace_destroyed_events: Vec<&'a dyn Fn(WorkspaceId)>,
-    active_monitor_changed_events: Vec<&'a dyn Fn(MonitorEventData)>,
-    active_window_changed_events: Vec<&'a dyn Fn(Option<WindowEventData>)>,
-    fullscreen_state_changed_events: Vec<&'a dyn Fn(bool)>,
-    monitor_removed_events: Vec<&'a dyn Fn(String)>,
-    monitor_added_events: Vec<&'a dyn Fn(String)>,
+pub struct EventListener {
+    next_id: usize,
+    workspace_changed_events: Vec<(usize, Handler<WorkspaceId>)>,
+    workspace_added_events: Vec<(usize, Handler<WorkspaceId>)>,
+    workspace_destroyed_events: Vec<(usize, Handler<WorkspaceId>)>,
+    workspace_moved_events: Vec<(usize, Handler<MonitorEventData>)>,
+    active_monitor_changed_events: Vec<(usize, Handler<MonitorEventData>)>,
+    active_window_changed_events: Vec<(usize, Handler<Option<WindowEventData>>)>,
+    fullscreen_state_changed_events: Vec<(usize, Handler<bool>)>,
+    monitor_removed_events: Vec<(usize, Handler<String>)>,
+    monitor_added_events: Vec<(usize, Handler<String>)>,
+    layout_changed_events: Vec<(usize, Handler<LayoutEvent>)>,
+    submap_changed_events: Vec<(usize, Handler<String>)>,
+    window_opened_events: Vec<(usize, Handler<WindowOpenEvent>)>,
+    window_closed_events: Vec<(usize, Handler<String>)>,
+    window_moved_events: Vec<(usize, Handler<WindowMoveEvent>)>,
+    urgent_state_events: Vec<(usize, Handler<String>)>,
 }
 
-impl EventListener<'_> {
+impl Default for EventListener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventListener {
     /// This method creates a new EventListener instance
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
     /// ```
-    pub fn new() -> EventListener<'static> {
+    pub fn new() -> EventListener {
         EventListener {
+            next_id: 0,
             workspace_changed_events: vec![],
             workspace_added_events: vec![],
             workspace_destroyed_events: vec![],
+            workspace_moved_events: vec![],
             active_monitor_changed_events: vec![],
             active_window_changed_events: vec![],
             fullscreen_state_changed_events: vec![],
             monitor_removed_events: vec![],
             monitor_added_events: vec![],
+            layout_changed_events: vec![],
+            submap_changed_events: vec![],
+            window_opened_events: vec![],
+            window_closed_events: vec![],
+            window_moved_events: vec![],
+            urgent_state_events: vec![],
         }
     }
+
+    /// Allocates the next [`HandlerId`] for the given category
+    fn next_handler_id(&mut self, category: HandlerCategory) -> HandlerId {
+        let id = self.next_id;
+        self.next_id += 1;
+        HandlerId(id, category)
+    }
+
+    /// Removes a previously registered handler by the [`HandlerId`] its
+    /// `add_*_handler` call returned, so a handler tied to a transient UI
+    /// element can be torn down once that element disappears
+    pub fn remove_handler(&mut self, id: HandlerId) {
+        match id.1 {
+            HandlerCategory::WorkspaceChanged => {
+                self.workspace_changed_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::WorkspaceAdded => {
+                self.workspace_added_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::WorkspaceDestroyed => self
+                .workspace_destroyed_events
+                .retain(|(hid, _)| *hid != id.0),
+            HandlerCategory::WorkspaceMoved => {
+                self.workspace_moved_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::ActiveMonitorChanged => self
+                .active_monitor_changed_events
+                .retain(|(hid, _)| *hid != id.0),
+            HandlerCategory::ActiveWindowChanged => self
+                .active_window_changed_events
+                .retain(|(hid, _)| *hid != id.0),
+            HandlerCategory::FullscreenStateChanged => self
+                .fullscreen_state_changed_events
+                .retain(|(hid, _)| *hid != id.0),
+            HandlerCategory::MonitorAdded => {
+                self.monitor_added_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::MonitorRemoved => {
+                self.monitor_removed_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::LayoutChanged => {
+                self.layout_changed_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::SubmapChanged => {
+                self.submap_changed_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::WindowOpened => {
+                self.window_opened_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::WindowClosed => {
+                self.window_closed_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::WindowMoved => {
+                self.window_moved_events.retain(|(hid, _)| *hid != id.0)
+            }
+            HandlerCategory::UrgentState => {
+                self.urgent_state_events.retain(|(hid, _)| *hid != id.0)
+            }
+        }
+    }
+
+    /// This method adds a event to the listener which executes on workspace change
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_workspace_change_handler(|id| println!("changed workspace to {id}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_workspace_change_handler(&mut self, f: impl FnMut(WorkspaceId) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WorkspaceChanged);
+        self.workspace_changed_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
     /// This method adds a event to the listener which executes on workspace change
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_workspace_change_handler_mut(|id, state| {
+    ///     state.active_workspace = id;
+    /// });
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_workspace_change_handler_mut(
+        &mut self,
+        f: impl FnMut(WorkspaceId, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WorkspaceChanged);
+        self.workspace_changed_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a new workspace is created
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_workspace_added_handler(|id| println!("workspace {id} was added"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_workspace_added_handler(&mut self, f: impl FnMut(WorkspaceId) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WorkspaceAdded);
+        self.workspace_added_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a new workspace is created
+    /// and is also given mutable access to the listener's shared [`State`]
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_workspace_change_handler(&|id| println!("changed workspace to {id}"));
+    /// listener.add_workspace_added_handler_mut(|id, state| println!("workspace {id} was added, was on {}", state.active_workspace));
     /// listener.start_listener_blocking()
     /// ```
-    pub fn add_workspace_change_handler(&mut self, f: &'static dyn Fn(WorkspaceId)) {
-        self.workspace_changed_events.push(f);
+    pub fn add_workspace_added_handler_mut(
+        &mut self,
+        f: impl FnMut(WorkspaceId, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WorkspaceAdded);
+        self.workspace_added_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
     }
 
     /// This method add a event to the listener which executes when a new workspace is created
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_workspace_added_handler(&|id| println!("workspace {id} was added"));
+    /// listener.add_workspace_destroy_handler(|id| println!("workspace {id} was destroyed"));
     /// listener.start_listener_blocking()
     /// ```
-    pub fn add_workspace_added_handler(&mut self, f: &'static dyn Fn(WorkspaceId)) {
-        self.workspace_added_events.push(f);
+    pub fn add_workspace_destroy_handler(&mut self, f: impl FnMut(WorkspaceId) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WorkspaceDestroyed);
+        self.workspace_destroyed_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
     }
 
     /// This method add a event to the listener which executes when a new workspace is created
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_workspace_destroy_handler_mut(|id, state| println!("workspace {id} was destroyed"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_workspace_destroy_handler_mut(
+        &mut self,
+        f: impl FnMut(WorkspaceId, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WorkspaceDestroyed);
+        self.workspace_destroyed_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when the active monitor is changed
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_workspace_destroy_handler(&|id| println!("workspace {id} was destroyed"));
+    /// listener.add_active_monitor_change_handler(|data| println!("Active Monitor changed: {data:#?}"));
     /// listener.start_listener_blocking()
     /// ```
-    pub fn add_workspace_destroy_handler(&mut self, f: &'static dyn Fn(WorkspaceId)) {
-        self.workspace_destroyed_events.push(f);
+    pub fn add_active_monitor_change_handler(
+        &mut self,
+        f: impl FnMut(MonitorEventData) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::ActiveMonitorChanged);
+        self.active_monitor_changed_events
+            .push((id.0, Handler::Regular(Box::new(f))));
+        id
     }
 
     /// This method add a event to the listener which executes when the active monitor is changed
+    /// and is also given mutable access to the listener's shared [`State`]
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_active_monitor_change_handler(&|data| println!("Active Monitor changed: {data:#?}"));
+    /// listener.add_active_monitor_change_handler_mut(|data, state| println!("Active Monitor changed: {data:#?}, was {}", state.active_monitor));
     /// listener.start_listener_blocking()
     /// ```
-    pub fn add_active_monitor_change_handler(&mut self, f: &'static dyn Fn(MonitorEventData)) {
-        self.active_monitor_changed_events.push(f);
+    pub fn add_active_monitor_change_handler_mut(
+        &mut self,
+        f: impl FnMut(MonitorEventData, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::ActiveMonitorChanged);
+        self.active_monitor_changed_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
     }
 
     /// This method add a event to the listener which executes when the active window is changed
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_active_window_change_handler(&|data| println!("Active window changed: {data:#?}"));
+    /// listener.add_active_window_change_handler(|data| println!("Active window changed: {data:#?}"));
     /// listener.start_listener_blocking()
     /// ```
     pub fn add_active_window_change_handler(
         &mut self,
-        f: &'static dyn Fn(Option<WindowEventData>),
-    ) {
-        self.active_window_changed_events.push(f);
+        f: impl FnMut(Option<WindowEventData>) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::ActiveWindowChanged);
+        self.active_window_changed_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when the active window is changed
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_active_window_change_handler_mut(|data, state| println!("Active window changed: {data:#?}, fullscreen: {}", state.fullscreen));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_active_window_change_handler_mut(
+        &mut self,
+        f: impl FnMut(Option<WindowEventData>, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::ActiveWindowChanged);
+        self.active_window_changed_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when the fullscreen state changes
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_fullscreen_state_change_handler(|state| println!("Fullscreen is on: {state}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_fullscreen_state_change_handler(&mut self, f: impl FnMut(bool) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::FullscreenStateChanged);
+        self.fullscreen_state_changed_events
+            .push((id.0, Handler::Regular(Box::new(f))));
+        id
     }
 
     /// This method add a event to the listener which executes when the active monitor is changed
+    /// and is also given mutable access to the listener's shared [`State`]
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_fullscreen_state_change_handler(&|state| println!("Fullscreen is on: {state}"));
+    /// listener.add_fullscreen_state_change_handler_mut(|new_state, state| println!("Fullscreen is on: {new_state}, window: {:?}", state.active_window));
     /// listener.start_listener_blocking()
     /// ```
-    pub fn add_fullscreen_state_change_handler(&mut self, f: &'static dyn Fn(bool)) {
-        self.fullscreen_state_changed_events.push(f);
+    pub fn add_fullscreen_state_change_handler_mut(
+        &mut self,
+        f: impl FnMut(bool, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::FullscreenStateChanged);
+        self.fullscreen_state_changed_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a new monitor is added
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_monitor_added_handler(|data| println!("Monitor added: {data}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_monitor_added_handler(&mut self, f: impl FnMut(String) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::MonitorAdded);
+        self.monitor_added_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
     }
 
     /// This method add a event to the listener which executes when a new monitor is added
+    /// and is also given mutable access to the listener's shared [`State`]
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_monitor_added_handler(&|data| println!("Monitor added: {data}"));
+    /// listener.add_monitor_added_handler_mut(|data, state| println!("Monitor added: {data}"));
     /// listener.start_listener_blocking()
     /// ```
-    pub fn add_monitor_added_handler(&mut self, f: &'static dyn Fn(String)) {
-        self.monitor_added_events.push(f);
+    pub fn add_monitor_added_handler_mut(&mut self, f: impl FnMut(String, &mut State) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::MonitorAdded);
+        self.monitor_added_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
     }
 
     /// This method add a event to the listener which executes when a monitor is removed
     ///
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_monitor_removed_handler(&|data| println!("Monitor removed: {data}"));
+    /// listener.add_monitor_removed_handler(|data| println!("Monitor removed: {data}"));
     /// listener.start_listener_blocking()
     /// ```
-    pub fn add_monitor_removed_handler(&mut self, f: &'static dyn Fn(String)) {
-        self.monitor_removed_events.push(f);
+    pub fn add_monitor_removed_handler(&mut self, f: impl FnMut(String) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::MonitorRemoved);
+        self.monitor_removed_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a monitor is removed
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_monitor_removed_handler_mut(|data, state| println!("Monitor removed: {data}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_monitor_removed_handler_mut(&mut self, f: impl FnMut(String, &mut State) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::MonitorRemoved);
+        self.monitor_removed_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a workspace is moved to a
+    /// different monitor
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_workspace_moved_handler(|data| println!("Workspace moved: {data:#?}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_workspace_moved_handler(&mut self, f: impl FnMut(MonitorEventData) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WorkspaceMoved);
+        self.workspace_moved_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a workspace is moved to a
+    /// different monitor and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_workspace_moved_handler_mut(|data, state| println!("Workspace moved: {data:#?}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_workspace_moved_handler_mut(
+        &mut self,
+        f: impl FnMut(MonitorEventData, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WorkspaceMoved);
+        self.workspace_moved_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when the keyboard layout changes
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_layout_change_handler(|data| println!("Layout changed: {data:#?}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_layout_change_handler(&mut self, f: impl FnMut(LayoutEvent) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::LayoutChanged);
+        self.layout_changed_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when the keyboard layout changes
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_layout_change_handler_mut(|data, state| println!("Layout changed: {data:#?}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_layout_change_handler_mut(
+        &mut self,
+        f: impl FnMut(LayoutEvent, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::LayoutChanged);
+        self.layout_changed_events
+            .push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when the active submap changes
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_submap_change_handler(|submap| println!("Submap changed to {submap}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_submap_change_handler(&mut self, f: impl FnMut(String) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::SubmapChanged);
+        self.submap_changed_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when the active submap changes
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_submap_change_handler_mut(|submap, state| println!("Submap changed to {submap}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_submap_change_handler_mut(
+        &mut self,
+        f: impl FnMut(String, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::SubmapChanged);
+        self.submap_changed_events
+            .push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a window is opened
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_window_open_handler(|data| println!("Window opened: {data:#?}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_window_open_handler(&mut self, f: impl FnMut(WindowOpenEvent) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WindowOpened);
+        self.window_opened_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a window is opened
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_window_open_handler_mut(|data, state| println!("Window opened: {data:#?}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_window_open_handler_mut(
+        &mut self,
+        f: impl FnMut(WindowOpenEvent, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WindowOpened);
+        self.window_opened_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a window is closed
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_window_close_handler(|address| println!("Window closed: {address}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_window_close_handler(&mut self, f: impl FnMut(String) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WindowClosed);
+        self.window_closed_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a window is closed
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_window_close_handler_mut(|address, state| println!("Window closed: {address}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_window_close_handler_mut(
+        &mut self,
+        f: impl FnMut(String, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WindowClosed);
+        self.window_closed_events
+            .push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a window is moved to a
+    /// different workspace
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_window_moved_handler(|data| println!("Window moved: {data:#?}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_window_moved_handler(&mut self, f: impl FnMut(WindowMoveEvent) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WindowMoved);
+        self.window_moved_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a window is moved to a
+    /// different workspace and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_window_moved_handler_mut(|data, state| println!("Window moved: {data:#?}"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_window_moved_handler_mut(
+        &mut self,
+        f: impl FnMut(WindowMoveEvent, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::WindowMoved);
+        self.window_moved_events.push((id.0, Handler::Mutable(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a window demands attention
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_urgent_state_handler(|address| println!("Window {address} is urgent"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_urgent_state_handler(&mut self, f: impl FnMut(String) + Send + 'static) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::UrgentState);
+        self.urgent_state_events.push((id.0, Handler::Regular(Box::new(f))));
+        id
+    }
+
+    /// This method add a event to the listener which executes when a window demands attention
+    /// and is also given mutable access to the listener's shared [`State`]
+    ///
+    /// ```rust
+    /// let mut listener = EventListener::new();
+    /// listener.add_urgent_state_handler_mut(|address, state| println!("Window {address} is urgent"));
+    /// listener.start_listener_blocking()
+    /// ```
+    pub fn add_urgent_state_handler_mut(
+        &mut self,
+        f: impl FnMut(String, &mut State) + Send + 'static,
+    ) -> HandlerId {
+        let id = self.next_handler_id(HandlerCategory::UrgentState);
+        self.urgent_state_events
+            .push((id.0, Handler::Mutable(Box::new(f))));
+        id
     }
 
     /// This method starts the event listener (async)
@@ -275,88 +914,135 @@ impl EventListener<'_> {
     /// This should be ran after all of your handlers are defined
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_workspace_change_handler(&|id| println!("changed workspace to {id}"));
+    /// listener.add_workspace_change_handler(|id| println!("changed workspace to {id}"));
     /// listener.start_listener().await
     /// ```
-    pub async fn start_listener(&self) -> io::Result<()> {
+    pub async fn start_listener(&mut self) -> io::Result<()> {
         let socket_path = get_socket_path(SocketType::Listener);
 
-        let mut stream = UnixStream::connect(socket_path).await?;
-
-        let mut buf = [0; 4096];
+        let stream = UnixStream::connect(socket_path).await?;
+        let mut lines = BufReader::new(stream).lines();
 
-        loop {
-            stream.readable().await?;
-            let num_read = stream.read(&mut buf).await?;
-            if num_read == 0 {
-                break;
-            }
-            let buf = &buf[..num_read];
+        let mut state = State::default();
 
-            let string = match String::from_utf8(buf.to_vec()) {
-                Ok(str) => str,
-                Err(error) => panic!("a error has occured {error:#?}"),
-            };
-
-            let parsed: Vec<Event> = match event_parser(string) {
+        while let Some(line) = lines.next_line().await? {
+            let parsed: Vec<Event> = match event_parser(line) {
                 Ok(vec) => vec,
                 Err(error) => panic!("a error has occured {error:#?}"),
             };
 
             for event in parsed.iter() {
+                state.update(event);
+
                 match event {
                     Event::WorkspaceChanged(id) => {
-                        let events = &self.workspace_changed_events;
-                        for item in events.iter() {
-                            item(*id)
+                        let events = &mut self.workspace_changed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(*id, &mut state)
                         }
                     }
                     Event::WorkspaceAdded(id) => {
-                        let events = &self.workspace_added_events;
-                        for item in events.iter() {
-                            item(*id)
+                        let events = &mut self.workspace_added_events;
+                        for item in events.iter_mut() {
+                            item.1.call(*id, &mut state)
                         }
                     }
                     Event::WorkspaceDeleted(id) => {
-                        let events = &self.workspace_destroyed_events;
-                        for item in events.iter() {
-                            item(*id)
+                        let events = &mut self.workspace_destroyed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(*id, &mut state)
                         }
                     }
                     Event::ActiveMonitorChanged(MonitorEventData(monitor, id)) => {
-                        let events = &self.active_monitor_changed_events;
-                        for item in events.iter() {
-                            item(MonitorEventData(monitor.clone(), *id))
+                        let events = &mut self.active_monitor_changed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(MonitorEventData(monitor.clone(), *id), &mut state)
                         }
                     }
                     Event::ActiveWindowChanged(Some(WindowEventData(class, title))) => {
-                        let events = &self.active_window_changed_events;
-                        for item in events.iter() {
-                            item(Some(WindowEventData(class.clone(), title.clone())))
+                        let events = &mut self.active_window_changed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(
+                                Some(WindowEventData(class.clone(), title.clone())),
+                                &mut state,
+                            )
                         }
                     }
                     Event::ActiveWindowChanged(None) => {
-                        let events = &self.active_window_changed_events;
-                        for item in events.iter() {
-                            item(None)
+                        let events = &mut self.active_window_changed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(None, &mut state)
                         }
                     }
                     Event::FullscreenStateChanged(bool) => {
-                        let events = &self.fullscreen_state_changed_events;
-                        for item in events.iter() {
-                            item(*bool)
+                        let events = &mut self.fullscreen_state_changed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(*bool, &mut state)
                         }
                     }
                     Event::MonitorAdded(monitor) => {
-                        let events = &self.monitor_added_events;
-                        for item in events.iter() {
-                            item(monitor.clone())
+                        let events = &mut self.monitor_added_events;
+                        for item in events.iter_mut() {
+                            item.1.call(monitor.clone(), &mut state)
                         }
                     }
                     Event::MonitorRemoved(monitor) => {
-                        let events = &self.monitor_removed_events;
-                        for item in events.iter() {
-                            item(monitor.clone())
+                        let events = &mut self.monitor_removed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(monitor.clone(), &mut state)
+                        }
+                    }
+                    Event::WorkspaceMoved(MonitorEventData(monitor, id)) => {
+                        let events = &mut self.workspace_moved_events;
+                        for item in events.iter_mut() {
+                            item.1.call(MonitorEventData(monitor.clone(), *id), &mut state)
+                        }
+                    }
+                    Event::LayoutChanged(LayoutEvent(keyboard, layout)) => {
+                        let events = &mut self.layout_changed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(LayoutEvent(keyboard.clone(), layout.clone()), &mut state)
+                        }
+                    }
+                    Event::SubmapChanged(submap) => {
+                        let events = &mut self.submap_changed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(submap.clone(), &mut state)
+                        }
+                    }
+                    Event::WindowOpened(WindowOpenEvent(address, workspace, class, title)) => {
+                        let events = &mut self.window_opened_events;
+                        for item in events.iter_mut() {
+                            item.1.call(
+                                WindowOpenEvent(
+                                    address.clone(),
+                                    workspace.clone(),
+                                    class.clone(),
+                                    title.clone(),
+                                ),
+                                &mut state,
+                            )
+                        }
+                    }
+                    Event::WindowClosed(address) => {
+                        let events = &mut self.window_closed_events;
+                        for item in events.iter_mut() {
+                            item.1.call(address.clone(), &mut state)
+                        }
+                    }
+                    Event::WindowMoved(WindowMoveEvent(address, workspace)) => {
+                        let events = &mut self.window_moved_events;
+                        for item in events.iter_mut() {
+                            item.1.call(
+                                WindowMoveEvent(address.clone(), workspace.clone()),
+                                &mut state,
+                            )
+                        }
+                    }
+                    Event::Urgent(address) => {
+                        let events = &mut self.urgent_state_events;
+                        for item in events.iter_mut() {
+                            item.1.call(address.clone(), &mut state)
                         }
                     }
                 }
@@ -371,14 +1057,134 @@ impl EventListener<'_> {
     /// This should be ran after all of your handlers are defined
     /// ```rust
     /// let mut listener = EventListener::new();
-    /// listener.add_workspace_change_handler(&|id| println!("changed workspace to {id}"));
+    /// listener.add_workspace_change_handler(|id| println!("changed workspace to {id}"));
     /// listener.start_listener_blocking()
     /// ```
-    pub fn start_listener_blocking(self) -> io::Result<()> {
+    pub fn start_listener_blocking(mut self) -> io::Result<()> {
         use tokio::runtime::Runtime;
 
         let rt = Runtime::new()?;
 
         rt.block_on(self.start_listener())
     }
+
+    /// Turns this listener into a [`Stream`] of parsed [`Event`]s, driven by the same
+    /// buffered socket reader as [`EventListener::start_listener`]
+    ///
+    /// This is an alternative to registering `FnMut` callbacks: consumers can
+    /// `while let Some(ev) = stream.next().await` and match on borrowed state of their
+    /// own, or compose the stream with `select!`.
+    ///
+    /// ```rust
+    /// use futures::StreamExt;
+    ///
+    /// let listener = EventListener::new();
+    /// let mut stream = Box::pin(listener.into_stream());
+    /// while let Some(event) = stream.next().await {
+    ///     println!("{event:#?}");
+    /// }
+    /// ```
+    pub fn into_stream(self) -> impl Stream<Item = io::Result<Event>> {
+        async_stream::try_stream! {
+            let socket_path = get_socket_path(SocketType::Listener);
+
+            let stream = UnixStream::connect(socket_path).await?;
+            let mut lines = BufReader::new(stream).lines();
+
+            while let Some(line) = lines.next_line().await? {
+                for event in event_parser(line)? {
+                    yield event;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(line: &str) -> Event {
+        let mut events = event_parser(line.to_string()).expect("should parse");
+        assert_eq!(events.len(), 1, "expected exactly one event from {line:?}");
+        events.remove(0)
+    }
+
+    #[test]
+    fn workspace_changed_defaults_to_one_when_empty() {
+        match parse_one("workspace>>") {
+            Event::WorkspaceChanged(workspace) => assert_eq!(workspace, 1),
+            other => panic!("unexpected event: {other:#?}"),
+        }
+    }
+
+    #[test]
+    fn moveworkspace_does_not_collide_with_workspace_changed() {
+        match parse_one("moveworkspace>>3,DP-1") {
+            Event::WorkspaceMoved(MonitorEventData(monitor, workspace)) => {
+                assert_eq!(monitor, "DP-1");
+                assert_eq!(workspace, 3);
+            }
+            other => panic!("unexpected event: {other:#?}"),
+        }
+    }
+
+    #[test]
+    fn layout_changed_with_comma_in_layout_name() {
+        match parse_one("activelayout>>kbd-name,English (US), variant") {
+            Event::LayoutChanged(LayoutEvent(keyboard, layout)) => {
+                assert_eq!(keyboard, "kbd-name");
+                assert_eq!(layout, "English (US), variant");
+            }
+            other => panic!("unexpected event: {other:#?}"),
+        }
+    }
+
+    #[test]
+    fn submap_changed() {
+        match parse_one("submap>>resize") {
+            Event::SubmapChanged(submap) => assert_eq!(submap, "resize"),
+            other => panic!("unexpected event: {other:#?}"),
+        }
+    }
+
+    #[test]
+    fn window_opened_with_comma_in_title() {
+        match parse_one("openwindow>>0xdeadbeef,1,code,file.txt, My Project - Visual Studio Code") {
+            Event::WindowOpened(WindowOpenEvent(address, workspace, class, title)) => {
+                assert_eq!(address, "0xdeadbeef");
+                assert_eq!(workspace, "1");
+                assert_eq!(class, "code");
+                assert_eq!(title, "file.txt, My Project - Visual Studio Code");
+            }
+            other => panic!("unexpected event: {other:#?}"),
+        }
+    }
+
+    #[test]
+    fn window_closed() {
+        match parse_one("closewindow>>0xdeadbeef") {
+            Event::WindowClosed(address) => assert_eq!(address, "0xdeadbeef"),
+            other => panic!("unexpected event: {other:#?}"),
+        }
+    }
+
+    #[test]
+    fn window_moved() {
+        match parse_one("movewindow>>0xdeadbeef,2") {
+            Event::WindowMoved(WindowMoveEvent(address, workspace)) => {
+                assert_eq!(address, "0xdeadbeef");
+                assert_eq!(workspace, "2");
+            }
+            other => panic!("unexpected event: {other:#?}"),
+        }
+    }
+
+    #[test]
+    fn urgent() {
+        match parse_one("urgent>>0xdeadbeef") {
+            Event::Urgent(address) => assert_eq!(address, "0xdeadbeef"),
+            other => panic!("unexpected event: {other:#?}"),
+        }
+    }
 }